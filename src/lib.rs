@@ -1,34 +1,358 @@
-use std::collections::HashMap;
+//! Builds with `std` by default (the `HashMap`-based trie/automaton and
+//! their `save_to`/`load_from` file helpers need it). Disabling default
+//! features (`no-default-features`) compiles the crate as `#![no_std]`,
+//! leaving only the const-generic, allocation-free `FixedRingBuffer` and
+//! `FixedAcAutomaton` below for embedded/sandboxed use.
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+// Every type in this crate is private and only exercised by the test suite
+// below, so the non-test build sees all of it as unused; that's expected
+// for a crate with no public API yet.
+#![allow(dead_code)]
 
 ///
+/// FixedRingBuffer
+///
+/// `no_std`, allocation-free counterpart of `RingBuffer`: backed by a
+/// fixed `[T; N]` array instead of a `Vec`, so `insert`/`get` never
+/// allocate. `N` must be a power of two, asserted in `new`.
+#[derive(Debug, Clone, Copy)]
+struct FixedRingBuffer<T: Copy + Default, const N: usize> {
+    buffer: [T; N],
+    pos: usize,
+}
+
+/// Backward cursor over a `FixedRingBuffer<T, N>`.
+#[derive(Debug, Clone, Copy)]
+struct FixedBackwardCursor<const N: usize> {
+    idx: usize,
+}
+
+impl<const N: usize> FixedBackwardCursor<N> {
+    fn next(&mut self) -> usize {
+        self.idx += N - 1;
+        self.idx &= N - 1;
+        self.idx
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for FixedRingBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> FixedRingBuffer<T, N> {
+    fn new() -> Self {
+        assert!(N.is_power_of_two(), "FixedRingBuffer capacity must be a power of two");
+        FixedRingBuffer {
+            buffer: [T::default(); N],
+            pos: 0,
+        }
+    }
+
+    fn insert(&mut self, value: T) {
+        self.buffer[self.pos] = value;
+        self.pos += 1;
+        self.pos &= N - 1;
+    }
+
+    fn cursor(&self) -> FixedBackwardCursor<N> {
+        FixedBackwardCursor { idx: self.pos }
+    }
+
+    fn get(&self, index: usize) -> T {
+        self.buffer[index]
+    }
+}
+
+///
+/// FixedAcAutomaton
+///
+/// `no_std`, allocation-free Aho-Corasick automaton: nodes live in a
+/// caller-provided arena slice (typically built once from a `&'static`
+/// pattern list) instead of a `Vec`, and each node's children are a
+/// linearly-scanned, `C`-capacity array instead of a `HashMap`. `step`
+/// never allocates.
+#[derive(Debug, Clone, Copy)]
+struct FixedAcNode<const C: usize> {
+    child_chars: [char; C],
+    child_indices: [usize; C],
+    child_count: usize,
+    fail: usize,
+    is_output: bool,
+    /// Next node in the BFS queue used by `build_failure_links`, an
+    /// intrusive linked list threaded through the arena so the queue
+    /// needs no storage beyond the nodes themselves.
+    bfs_next: Option<usize>,
+}
+
+impl<const C: usize> Default for FixedAcNode<C> {
+    fn default() -> Self {
+        FixedAcNode {
+            child_chars: ['\0'; C],
+            child_indices: [0; C],
+            child_count: 0,
+            fail: 0,
+            is_output: false,
+            bfs_next: None,
+        }
+    }
+}
+
+impl<const C: usize> FixedAcNode<C> {
+    fn child(&self, ch: char) -> Option<usize> {
+        (0..self.child_count).find_map(|i| (self.child_chars[i] == ch).then_some(self.child_indices[i]))
+    }
+}
+
+struct FixedAcAutomaton<'a, const C: usize> {
+    nodes: &'a mut [FixedAcNode<C>],
+    len: usize,
+}
+
+impl<'a, const C: usize> FixedAcAutomaton<'a, C> {
+    const ROOT: usize = 0;
+
+    /// Builds the automaton into `arena`. Returns `None` if `patterns`
+    /// need more nodes than `arena` holds, or any node needs more than
+    /// `C` distinct children.
+    fn build(patterns: &[&str], arena: &'a mut [FixedAcNode<C>]) -> Option<Self> {
+        if arena.is_empty() {
+            return None;
+        }
+        arena[0] = FixedAcNode::default();
+        let mut automaton = FixedAcAutomaton { nodes: arena, len: 1 };
+        for pattern in patterns {
+            automaton.insert(pattern)?;
+        }
+        automaton.build_failure_links();
+        Some(automaton)
+    }
+
+    fn insert(&mut self, pattern: &str) -> Option<()> {
+        let mut node = Self::ROOT;
+        for ch in pattern.chars() {
+            node = match self.nodes[node].child(ch) {
+                Some(next) => next,
+                None => {
+                    if self.len >= self.nodes.len() || self.nodes[node].child_count >= C {
+                        return None;
+                    }
+                    let child = self.len;
+                    self.nodes[child] = FixedAcNode::default();
+                    let count = self.nodes[node].child_count;
+                    self.nodes[node].child_chars[count] = ch;
+                    self.nodes[node].child_indices[count] = child;
+                    self.nodes[node].child_count += 1;
+                    self.len += 1;
+                    child
+                }
+            };
+        }
+        self.nodes[node].is_output = true;
+        Some(())
+    }
+
+    /// Computes failure links (and merges `is_output` across them) in
+    /// genuine BFS/depth order: `is_output(v) |= is_output(fail(v))` is
+    /// only correct once `fail(v)`'s own merge has already run, which
+    /// creation-chronology order does not guarantee - a later-inserted
+    /// pattern can create a shallow node (some earlier node's failure
+    /// target) with a larger arena index than an earlier-inserted,
+    /// deeper one. The queue is an intrusive linked list threaded through
+    /// `FixedAcNode::bfs_next`, since there's no heap for a `VecDeque`.
+    /// A node that is still the queue's tail has `bfs_next == None` until
+    /// its own children are enqueued, so it's read after that, not before.
+    fn build_failure_links(&mut self) {
+        let mut tail: Option<usize> = None;
+        let enqueue = |nodes: &mut [FixedAcNode<C>], tail: &mut Option<usize>, idx: usize| {
+            nodes[idx].bfs_next = None;
+            if let Some(t) = *tail {
+                nodes[t].bfs_next = Some(idx);
+            }
+            *tail = Some(idx);
+        };
+
+        let root_child_count = self.nodes[Self::ROOT].child_count;
+        let mut head = None;
+        for i in 0..root_child_count {
+            let idx = self.nodes[Self::ROOT].child_indices[i];
+            self.nodes[idx].fail = Self::ROOT;
+            enqueue(self.nodes, &mut tail, idx);
+            if head.is_none() {
+                head = Some(idx);
+            }
+        }
+
+        let mut current = head;
+        while let Some(u) = current {
+            let child_count = self.nodes[u].child_count;
+            for i in 0..child_count {
+                let ch = self.nodes[u].child_chars[i];
+                let v = self.nodes[u].child_indices[i];
+
+                let mut f = self.nodes[u].fail;
+                while f != Self::ROOT && self.nodes[f].child(ch).is_none() {
+                    f = self.nodes[f].fail;
+                }
+                self.nodes[v].fail = match self.nodes[f].child(ch) {
+                    Some(next) if next != v => next,
+                    _ => Self::ROOT,
+                };
+                let fail = self.nodes[v].fail;
+                self.nodes[v].is_output |= self.nodes[fail].is_output;
+
+                enqueue(self.nodes, &mut tail, v);
+            }
+            current = self.nodes[u].bfs_next;
+        }
+    }
+
+    fn goto(&self, mut state: usize, ch: char) -> usize {
+        loop {
+            if let Some(next) = self.nodes[state].child(ch) {
+                return next;
+            }
+            if state == Self::ROOT {
+                return Self::ROOT;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    /// Advance from `state` by one character, returning the new state and
+    /// whether it completes a match.
+    fn step(&self, state: usize, ch: char) -> (usize, bool) {
+        let next = self.goto(state, ch);
+        (next, self.nodes[next].is_output)
+    }
+}
+
+#[cfg(test)]
+mod fixed_tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_ring_buffer() {
+        let mut ring: FixedRingBuffer<char, 4> = FixedRingBuffer::new();
+        ring.insert('a');
+        ring.insert('b');
+        ring.insert('c');
+        let mut cursor = ring.cursor();
+        assert_eq!(ring.get(cursor.next()), 'c');
+        assert_eq!(ring.get(cursor.next()), 'b');
+        assert_eq!(ring.get(cursor.next()), 'a');
+        assert_eq!(ring.get(cursor.next()), '\0');
+    }
+
+    #[test]
+    fn test_fixed_ac_automaton() {
+        let mut arena = [FixedAcNode::<4>::default(); 16];
+        let automaton = FixedAcAutomaton::build(&["he", "she"], &mut arena).unwrap();
+
+        let mut state = FixedAcAutomaton::<4>::ROOT;
+        let mut matched = false;
+        for ch in "xshex".chars() {
+            let (next, is_match) = automaton.step(state, ch);
+            state = next;
+            matched |= is_match;
+        }
+        assert!(matched);
+    }
+
+    #[test]
+    fn test_fixed_ac_automaton_arena_too_small() {
+        let mut arena = [FixedAcNode::<4>::default(); 2];
+        assert!(FixedAcAutomaton::build(&["he", "she"], &mut arena).is_none());
+    }
+
+    #[test]
+    fn test_fixed_ac_automaton_interleaved_lengths() {
+        // "baba" is inserted before "aa", so its depth-2..4 nodes get
+        // smaller arena indices than the depth-1 "a" node, and arena index
+        // order no longer matches depth/BFS order. Check every position's match flag against a
+        // brute-force substring scan, not just "some match fired".
+        let patterns = ["baba", "aa", "b", "abaa"];
+        let mut arena = [FixedAcNode::<4>::default(); 32];
+        let automaton = FixedAcAutomaton::build(&patterns, &mut arena).unwrap();
+
+        let input = "abab";
+        let mut state = FixedAcAutomaton::<4>::ROOT;
+        for (i, ch) in input.chars().enumerate() {
+            let (next, is_match) = automaton.step(state, ch);
+            state = next;
+
+            let end = i + 1;
+            let expected = patterns
+                .iter()
+                .any(|p| end >= p.len() && &input[end - p.len()..end] == *p);
+            assert_eq!(is_match, expected, "mismatch at position {}", i);
+        }
+    }
+
+    #[test]
+    fn test_fixed_ac_automaton_unbranched_chain() {
+        // "aaaa" gives the root a single, unbranched child chain, so
+        // `build_failure_links`'s BFS queue has no sibling to keep it
+        // alive past the first node - it must still visit every node in
+        // that chain as `u`, not just as someone else's `fail` target.
+        let patterns = ["aaaa", "a"];
+        let mut arena = [FixedAcNode::<4>::default(); 32];
+        let automaton = FixedAcAutomaton::build(&patterns, &mut arena).unwrap();
+
+        let input = "abbbaaaabaaabaababababaaababab";
+        let mut state = FixedAcAutomaton::<4>::ROOT;
+        for (i, ch) in input.chars().enumerate() {
+            let (next, is_match) = automaton.step(state, ch);
+            state = next;
+
+            let end = i + 1;
+            let expected = patterns
+                .iter()
+                .any(|p| end >= p.len() && &input[end - p.len()..end] == *p);
+            assert_eq!(is_match, expected, "mismatch at position {}", i);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_impl {
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    ///
 /// Trie
 ///
-#[derive(Default, Debug)]
-struct Trie {
+/// Generic over the stream element `T` so callers can match byte streams,
+/// tokenized words, or any other `Eq + Hash + Clone` element, not just `char`.
+#[derive(Debug)]
+struct Trie<T: Eq + Hash + Clone> {
     is_leaf: bool,
-    children: HashMap<char, Trie>,
+    children: HashMap<T, Trie<T>>,
 }
 
-impl Trie {
-    fn insert_str(&mut self, key: &str) {
+impl<T: Eq + Hash + Clone> Default for Trie<T> {
+    fn default() -> Self {
+        Trie {
+            is_leaf: false,
+            children: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> Trie<T> {
+    fn insert(&mut self, key: Vec<T>) {
         let mut node = self;
         // inverse as data stream got reversely
-        for ch in key.chars().rev() {
-            if !node.children.contains_key(&ch) {
-                node.children.insert(ch, Trie::default());
-            }
-            node = node.children.get_mut(&ch).unwrap();
+        for ch in key.into_iter().rev() {
+            node = node.children.entry(ch).or_default();
         }
         node.is_leaf = true;
     }
 
-    fn insert(&mut self, key: String) {
-        self.insert_str(&key);
-    }
-
-    fn query_str(&self, key: &str) -> bool {
+    fn query(&self, key: Vec<T>) -> bool {
         let mut node = self;
-        for ch in key.chars().rev() {
+        for ch in key.into_iter().rev() {
             let n = node.children.get(&ch);
             if let Some(node) = n {
                 if node.is_leaf {
@@ -42,18 +366,26 @@ impl Trie {
 
         false
     }
+}
 
-    fn query(&self, key: String) -> bool {
-        self.query_str(&key)
+impl Trie<char> {
+    fn insert_str(&mut self, key: &str) {
+        self.insert(key.chars().collect());
+    }
+
+    fn query_str(&self, key: &str) -> bool {
+        self.query(key.chars().collect())
     }
 }
 
 ///
 /// Ring
 ///
+/// Generic over the stored element `T`; `Default` supplies the fill value
+/// for unwritten slots.
 #[derive(Debug)]
-struct RingBuffer {
-    buffer: Vec<char>,
+struct RingBuffer<T> {
+    buffer: Vec<T>,
     len: usize,
     pos: usize,
 }
@@ -79,27 +411,27 @@ impl BackwardCursor {
     }
 }
 
-impl Default for RingBuffer {
+impl<T: Default + Clone> Default for RingBuffer<T> {
     fn default() -> Self {
         RingBuffer {
-            buffer: vec![' '; 1024],
+            buffer: vec![T::default(); 1024],
             len: 1024,
             pos: 0,
         }
     }
 }
-impl RingBuffer {
+impl<T: Default + Clone> RingBuffer<T> {
     fn new(n: usize) -> Self {
         let mut len = 2;
         while len < n {
             len += len;
         }
         let pos = 0;
-        let buffer = vec![' '; len];
+        let buffer = vec![T::default(); len];
         RingBuffer { buffer, len, pos }
     }
 
-    fn insert(&mut self, ch: char) {
+    fn insert(&mut self, ch: T) {
         self.buffer[self.pos] = ch;
         self.pos += 1;
         self.pos &= self.len - 1;
@@ -112,25 +444,281 @@ impl RingBuffer {
         }
     }
 
-    fn get(&self, index: usize) -> char {
-        self.buffer[index]
+    fn get(&self, index: usize) -> T {
+        self.buffer[index].clone()
     }
 }
 
 ///
-/// StreamAlerter
+/// Aho-Corasick automaton
 ///
+/// A forward trie over the inserted keys with failure links, so matches
+/// (including overlapping ones) are found in O(1) amortized per character
+/// instead of walking a reversed trie through a ring buffer.
 #[derive(Debug, Default)]
-struct StreamAlerter {
-    ring: RingBuffer,
-    trie: Trie,
+struct AcNode {
+    children: HashMap<char, usize>,
+    fail: usize,
+    /// Ids (into `AcAutomaton::keys`) of every pattern ending at this node,
+    /// once failure-link suffix matches are merged in.
+    output: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct AcAutomaton {
+    nodes: Vec<AcNode>,
+    /// Original patterns, indexed by the ids stored in `AcNode::output`.
+    keys: Vec<String>,
 }
-impl StreamAlerter {
+
+impl Default for AcAutomaton {
+    fn default() -> Self {
+        AcAutomaton {
+            nodes: vec![AcNode::default()],
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl AcAutomaton {
+    const ROOT: usize = 0;
+
+    fn insert(&mut self, key: &str) {
+        let id = self.keys.len();
+        self.keys.push(key.to_string());
+
+        let mut node = Self::ROOT;
+        for ch in key.chars() {
+            node = match self.nodes[node].children.get(&ch) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(AcNode::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(ch, child);
+                    child
+                }
+            };
+        }
+        self.nodes[node].output.push(id);
+    }
+
+    fn build_failure_links(&mut self) {
+        let mut queue = std::collections::VecDeque::new();
+        let children: Vec<(char, usize)> = self.nodes[Self::ROOT]
+            .children
+            .iter()
+            .map(|(&ch, &idx)| (ch, idx))
+            .collect();
+        for (_, idx) in children {
+            self.nodes[idx].fail = Self::ROOT;
+            queue.push_back(idx);
+        }
+
+        while let Some(u) = queue.pop_front() {
+            let children: Vec<(char, usize)> = self.nodes[u]
+                .children
+                .iter()
+                .map(|(&ch, &idx)| (ch, idx))
+                .collect();
+            for (ch, v) in children {
+                let mut f = self.nodes[u].fail;
+                while f != Self::ROOT && !self.nodes[f].children.contains_key(&ch) {
+                    f = self.nodes[f].fail;
+                }
+                self.nodes[v].fail = match self.nodes[f].children.get(&ch) {
+                    Some(&next) if next != v => next,
+                    _ => Self::ROOT,
+                };
+                let fail = self.nodes[v].fail;
+                let mut suffix_output = self.nodes[fail].output.clone();
+                self.nodes[v].output.append(&mut suffix_output);
+                queue.push_back(v);
+            }
+        }
+    }
+
+    fn goto(&self, mut state: usize, ch: char) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[state].children.get(&ch) {
+                return next;
+            }
+            if state == Self::ROOT {
+                return Self::ROOT;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+}
+
+/// A keyword match reported by `AcAlerter::query_match`: which key matched
+/// and the `[start, end)` stream offset it matched at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Match {
+    key: String,
+    len: usize,
+    start: usize,
+    end: usize,
+}
+
+///
+/// AcAlerter
+///
+/// Same API shape as `StreamAlerter`, but backed by an Aho-Corasick
+/// automaton instead of a reversed trie + ring buffer, so `push` is O(1)
+/// amortized per character and reports overlapping matches too.
+#[derive(Debug, Default)]
+struct AcAlerter {
+    automaton: AcAutomaton,
+    state: usize,
+    /// Count of characters pushed so far, used to compute `Match` offsets.
+    pos: usize,
+}
+
+impl AcAlerter {
     fn new(keys: Vec<String>) -> Self {
+        let mut automaton = AcAutomaton::default();
+        for key in keys {
+            automaton.insert(&key);
+        }
+        automaton.build_failure_links();
+        AcAlerter {
+            automaton,
+            state: AcAutomaton::ROOT,
+            pos: 0,
+        }
+    }
+
+    fn push(&mut self, ch: char) -> bool {
+        !self.query_match(ch).is_empty()
+    }
+
+    /// Advance the automaton by one character and report every keyword
+    /// (including overlapping ones) that ends at this position.
+    fn query_match(&mut self, ch: char) -> smallvec::SmallVec<[Match; 4]> {
+        self.state = self.automaton.goto(self.state, ch);
+        self.pos += 1;
+        let end = self.pos;
+        self.automaton.nodes[self.state]
+            .output
+            .iter()
+            .map(|&id| {
+                let key = self.automaton.keys[id].clone();
+                let len = key.chars().count();
+                Match {
+                    key,
+                    len,
+                    start: end - len,
+                    end,
+                }
+            })
+            .collect()
+    }
+
+    /// Write the compiled automaton out so it can be loaded back without
+    /// rebuilding the trie and failure links from the keyword list.
+    fn save_to<W: std::io::Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, &CompactAutomaton::from(&self.automaton))
+    }
+
+    /// Load a precompiled automaton written by `save_to`, ready to `push`
+    /// from its root state.
+    fn load_from<R: std::io::Read>(reader: R) -> serde_json::Result<Self> {
+        let compact: CompactAutomaton = serde_json::from_reader(reader)?;
+        Ok(AcAlerter {
+            automaton: AcAutomaton::from(compact),
+            state: AcAutomaton::ROOT,
+            pos: 0,
+        })
+    }
+}
+
+/// On-disk form of `AcAutomaton`. `AcNode::children` is a `HashMap<char,
+/// usize>`, which serde can't serialize compactly (or at all, as JSON map
+/// keys) - each node is instead stored as two parallel sorted arrays,
+/// rebuilt into a `HashMap` on load.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CompactAcNode {
+    child_chars: Vec<char>,
+    child_indices: Vec<usize>,
+    fail: usize,
+    output: Vec<usize>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CompactAutomaton {
+    nodes: Vec<CompactAcNode>,
+    keys: Vec<String>,
+}
+
+impl From<&AcAutomaton> for CompactAutomaton {
+    fn from(automaton: &AcAutomaton) -> Self {
+        let nodes = automaton
+            .nodes
+            .iter()
+            .map(|node| {
+                let mut children: Vec<(char, usize)> =
+                    node.children.iter().map(|(&c, &i)| (c, i)).collect();
+                children.sort_by_key(|&(c, _)| c);
+                let (child_chars, child_indices) = children.into_iter().unzip();
+                CompactAcNode {
+                    child_chars,
+                    child_indices,
+                    fail: node.fail,
+                    output: node.output.clone(),
+                }
+            })
+            .collect();
+        CompactAutomaton {
+            nodes,
+            keys: automaton.keys.clone(),
+        }
+    }
+}
+
+impl From<CompactAutomaton> for AcAutomaton {
+    fn from(compact: CompactAutomaton) -> Self {
+        let nodes = compact
+            .nodes
+            .into_iter()
+            .map(|n| AcNode {
+                children: n.child_chars.into_iter().zip(n.child_indices).collect(),
+                fail: n.fail,
+                output: n.output,
+            })
+            .collect();
+        AcAutomaton {
+            nodes,
+            keys: compact.keys,
+        }
+    }
+}
+
+///
+/// StreamAlerter
+///
+/// Generic over the stream element `T`, so it can scan `char` text, raw
+/// `u8` protocol bytes, or pre-tokenized word sequences alike.
+#[derive(Debug)]
+struct StreamAlerter<T: Eq + Hash + Clone + Default> {
+    ring: RingBuffer<T>,
+    trie: Trie<T>,
+}
+
+impl<T: Eq + Hash + Clone + Default> Default for StreamAlerter<T> {
+    fn default() -> Self {
+        StreamAlerter {
+            ring: RingBuffer::default(),
+            trie: Trie::default(),
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone + Default> StreamAlerter<T> {
+    fn new(keys: Vec<Vec<T>>) -> Self {
         let mut trie = Trie::default();
         let mut max_len = 0;
         for key in keys {
-            let v = key.chars().count();
+            let v = key.len();
             if v > max_len {
                 max_len = v;
             }
@@ -140,7 +728,7 @@ impl StreamAlerter {
         StreamAlerter { ring, trie }
     }
 
-    fn query(&mut self, ch: char) -> bool {
+    fn query(&mut self, ch: T) -> bool {
         self.ring.insert(ch);
         let mut node = &self.trie;
         let mut cursor = self.ring.cursor();
@@ -159,6 +747,162 @@ impl StreamAlerter {
     }
 }
 
+impl StreamAlerter<char> {
+    /// Convenience constructor for the common case of `char` keywords
+    /// given as `String`s.
+    fn from_strings(keys: Vec<String>) -> Self {
+        Self::new(keys.into_iter().map(|k| k.chars().collect()).collect())
+    }
+}
+
+///
+/// AtomicRing
+///
+/// A lock-free single-producer/single-consumer ring buffer, capacity
+/// rounded up to a power of two like `RingBuffer::new`. Only ever pushed
+/// from one thread and popped from another, so `head`/`tail` are each
+/// written by exactly one side, making `push`/`pop` wait-free.
+struct AtomicRing<T> {
+    buffer: Box<[std::cell::UnsafeCell<Option<T>>]>,
+    mask: usize,
+    head: std::sync::atomic::AtomicUsize,
+    tail: std::sync::atomic::AtomicUsize,
+}
+
+unsafe impl<T: Send> Sync for AtomicRing<T> {}
+
+impl<T> AtomicRing<T> {
+    fn with_capacity(n: usize) -> Self {
+        let mut len = 2;
+        while len < n {
+            len += len;
+        }
+        let buffer = (0..len)
+            .map(|_| std::cell::UnsafeCell::new(None))
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+        AtomicRing {
+            buffer,
+            mask: len - 1,
+            head: std::sync::atomic::AtomicUsize::new(0),
+            tail: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Producer-only: append one element; `false` if the ring is full.
+    fn push(&self, value: T) -> bool {
+        use std::sync::atomic::Ordering;
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head.wrapping_sub(tail) == self.capacity() {
+            return false;
+        }
+        unsafe {
+            *self.buffer[head & self.mask].get() = Some(value);
+        }
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        true
+    }
+
+    /// Consumer-only: take the oldest element, or `None` if empty.
+    fn pop(&self) -> Option<T> {
+        use std::sync::atomic::Ordering;
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail == head {
+            return None;
+        }
+        let slot = unsafe { &mut *self.buffer[tail & self.mask].get() };
+        let value = slot.take();
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        value
+    }
+}
+
+/// Write half of a `StreamChannel`: pushes elements from a producer
+/// thread (e.g. network/IO) with no locking.
+struct Feeder<T> {
+    ring: std::sync::Arc<AtomicRing<T>>,
+}
+
+impl<T> Feeder<T> {
+    fn push(&self, ch: T) -> bool {
+        self.ring.push(ch)
+    }
+
+    fn push_slice(&self, chs: &[T]) -> usize
+    where
+        T: Clone,
+    {
+        chs.iter()
+            .take_while(|ch| self.ring.push((*ch).clone()))
+            .count()
+    }
+}
+
+/// Read half of a `StreamChannel`: drains buffered elements and scans
+/// them through a `StreamAlerter`.
+struct Alerter<T: Eq + Hash + Clone + Default> {
+    ring: std::sync::Arc<AtomicRing<T>>,
+    alerter: StreamAlerter<T>,
+}
+
+impl<T: Eq + Hash + Clone + Default> Alerter<T> {
+    /// Drain everything currently buffered, returning one bool per
+    /// element: whether it completed a keyword match.
+    fn pop_iter(&mut self) -> Vec<bool> {
+        let mut matches = Vec::new();
+        while let Some(ch) = self.ring.pop() {
+            matches.push(self.alerter.query(ch));
+        }
+        matches
+    }
+}
+
+///
+/// StreamChannel
+///
+/// Producer/consumer split in the spirit of the `ringbuf` crate: call
+/// `split()` to get a `Feeder` that an IO thread can push into and an
+/// `Alerter` that a scanning thread drains and matches.
+struct StreamChannel<T: Eq + Hash + Clone + Default> {
+    keys: Vec<Vec<T>>,
+    capacity: usize,
+}
+
+impl<T: Eq + Hash + Clone + Default> StreamChannel<T> {
+    fn new(keys: Vec<Vec<T>>, capacity: usize) -> Self {
+        StreamChannel { keys, capacity }
+    }
+
+    fn split(self) -> (Feeder<T>, Alerter<T>) {
+        let ring = std::sync::Arc::new(AtomicRing::with_capacity(self.capacity));
+        let feeder = Feeder {
+            ring: std::sync::Arc::clone(&ring),
+        };
+        let alerter = Alerter {
+            ring,
+            alerter: StreamAlerter::new(self.keys),
+        };
+        (feeder, alerter)
+    }
+}
+
+impl StreamChannel<char> {
+    /// Convenience constructor for the common case of `char` keywords
+    /// given as `String`s.
+    fn from_strings(keys: Vec<String>, capacity: usize) -> Self {
+        Self::new(
+            keys.into_iter().map(|k| k.chars().collect()).collect(),
+            capacity,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,7 +931,8 @@ mod tests {
         assert_eq!(ring.get(cursor.next()), 'c');
         assert_eq!(ring.get(cursor.next()), 'b');
         assert_eq!(ring.get(cursor.next()), 'a');
-        assert_eq!(ring.get(cursor.next()), ' ');
+        // unwritten slots hold T::default(), '\0' for char
+        assert_eq!(ring.get(cursor.next()), '\0');
         ring.insert('d');
         let mut cursor = ring.cursor();
         assert_eq!(ring.get(cursor.next()), 'd');
@@ -204,7 +949,7 @@ mod tests {
 
     #[test]
     fn test_stream_alerter() {
-        let mut sa = StreamAlerter::new(vec![
+        let mut sa = StreamAlerter::from_strings(vec![
             "赌博".to_string(),
             "游戏".to_string(),
             "摇头丸".to_string(),
@@ -223,13 +968,106 @@ mod tests {
         assert!(sa.query('X'));
     }
 
+    #[test]
+    fn test_stream_alerter_bytes() {
+        // raw protocol scanning: match on `u8` instead of `char`.
+        let mut sa: StreamAlerter<u8> = StreamAlerter::new(vec![b"GET".to_vec(), b"PUT".to_vec()]);
+        for b in b"XXGE" {
+            assert!(!sa.query(*b));
+        }
+        assert!(sa.query(b'T'));
+    }
+
+    #[test]
+    fn test_stream_channel_split() {
+        let channel = StreamChannel::from_strings(vec!["abc".to_string()], 16);
+        let (feeder, mut alerter) = channel.split();
+
+        let producer = std::thread::spawn(move || {
+            for ch in "xxabcyy".chars() {
+                while !feeder.push(ch) {}
+            }
+        });
+        producer.join().unwrap();
+
+        let matches = alerter.pop_iter();
+        assert_eq!(matches.iter().filter(|&&m| m).count(), 1);
+    }
+
+    #[test]
+    fn test_stream_channel_push_slice() {
+        let channel = StreamChannel::from_strings(vec!["abc".to_string()], 16);
+        let (feeder, mut alerter) = channel.split();
+
+        let chars: Vec<char> = "xxabcyy".chars().collect();
+        let pushed = feeder.push_slice(&chars);
+        assert_eq!(pushed, chars.len());
+
+        let matches = alerter.pop_iter();
+        assert_eq!(matches.iter().filter(|&&m| m).count(), 1);
+    }
+
+    #[test]
+    fn test_ac_alerter() {
+        let mut ac = AcAlerter::new(vec![
+            "赌博".to_string(),
+            "游戏".to_string(),
+            "摇头丸".to_string(),
+            "XXX".to_string(),
+        ]);
+        assert!(!ac.push('a'));
+        assert!(!ac.push('赌'));
+        assert!(ac.push('博'));
+        assert!(!ac.push('游'));
+        assert!(ac.push('戏'));
+        assert!(!ac.push('摇'));
+        assert!(!ac.push('头'));
+        assert!(ac.push('丸'));
+        assert!(!ac.push('X'));
+        assert!(!ac.push('X'));
+        assert!(ac.push('X'));
+    }
+
+    #[test]
+    fn test_ac_alerter_overlap() {
+        // "he" and "she" overlap at the 'h'/'e' of "she"; both must fire.
+        let mut ac = AcAlerter::new(vec!["he".to_string(), "she".to_string()]);
+        assert!(!ac.push('s'));
+        assert!(!ac.push('h'));
+        assert!(ac.push('e'));
+    }
+
+    #[test]
+    fn test_ac_alerter_save_load_roundtrip() {
+        let ac = AcAlerter::new(vec!["he".to_string(), "she".to_string()]);
+        let mut bytes = Vec::new();
+        ac.save_to(&mut bytes).unwrap();
+
+        let mut loaded = AcAlerter::load_from(bytes.as_slice()).unwrap();
+        assert!(!loaded.push('s'));
+        assert!(!loaded.push('h'));
+        assert!(loaded.push('e'));
+    }
+
+    #[test]
+    fn test_ac_alerter_query_match_overlap() {
+        let mut ac = AcAlerter::new(vec!["he".to_string(), "she".to_string()]);
+        assert!(ac.query_match('s').is_empty());
+        assert!(ac.query_match('h').is_empty());
+
+        let matches = ac.query_match('e');
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().any(|m| m.key == "he" && m.start == 1 && m.end == 3));
+        assert!(matches.iter().any(|m| m.key == "she" && m.start == 0 && m.end == 3));
+    }
+
     #[test]
     fn test_stream_alerter_02() {
         use rand::distributions::Uniform;
         use rand::prelude::*;
 
         let mut rng = rand::thread_rng();
-        let mut sa = StreamAlerter::new(vec!["abc".to_string(), "xyz".to_string()]);
+        let mut sa = StreamAlerter::from_strings(vec!["abc".to_string(), "xyz".to_string()]);
 
         let mut count = 0;
         let uniform = Uniform::new(0u8, 26u8);
@@ -244,3 +1082,4 @@ mod tests {
         assert_ne!(count, 0)
     }
 }
+} // mod std_impl